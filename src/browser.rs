@@ -0,0 +1,46 @@
+use std::error::Error;
+use std::time::Duration;
+
+use fantoccini::{ClientBuilder, Locator};
+use url::Url;
+
+/// Environment variable holding the port `chromedriver`/`geckodriver` is listening on.
+/// Unset (or unparsable) means WebDriver rendering is disabled.
+const WEBDRIVER_PORT_ENV: &str = "WEBDRIVER_PORT";
+
+/// How long to wait for the DOM to settle after navigation before reading it back.
+const RENDER_SETTLE_TIME: Duration = Duration::from_secs(2);
+
+/// Fetches `url` through a WebDriver session so that JavaScript-injected players show up
+/// in the returned HTML, returning `None` when no WebDriver endpoint is configured so the
+/// caller can fall back to a plain `reqwest` fetch.
+pub async fn fetch_rendered_html(url: &Url) -> Result<Option<String>, Box<dyn Error>> {
+    let Some(port) = webdriver_port() else {
+        return Ok(None);
+    };
+
+    let html = render_with_webdriver(url, port).await?;
+    Ok(Some(html))
+}
+
+fn webdriver_port() -> Option<u16> {
+    std::env::var(WEBDRIVER_PORT_ENV).ok()?.parse().ok()
+}
+
+async fn render_with_webdriver(url: &Url, port: u16) -> Result<String, Box<dyn Error>> {
+    let webdriver_url = format!("http://localhost:{}", port);
+    let client = ClientBuilder::native().connect(&webdriver_url).await?;
+
+    let result = async {
+        client.goto(url.as_str()).await?;
+        // Give CMS-injected players (JWPlayer, Brightcove, etc.) time to mount.
+        tokio::time::sleep(RENDER_SETTLE_TIME).await;
+        // Waiting on <body> is a cheap proxy for "the DOM has settled".
+        client.wait().for_element(Locator::Css("body")).await?;
+        client.source().await
+    }
+    .await;
+
+    client.close().await?;
+    Ok(result?)
+}
@@ -0,0 +1,158 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+
+use crate::cookies;
+
+const CONCURRENCY_ENV: &str = "CRAWL_CONCURRENCY";
+const REQUEST_TIMEOUT_ENV: &str = "REQUEST_TIMEOUT_SECS";
+const USER_AGENT_ENV: &str = "CRAWL_USER_AGENT";
+const EXTRA_HEADERS_ENV: &str = "CRAWL_EXTRA_HEADERS";
+const COOKIES_FILE_ENV: &str = "CRAWL_COOKIES_FILE";
+
+const DEFAULT_CONCURRENCY: usize = 8;
+const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 30;
+const DEFAULT_USER_AGENT: &str =
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0 Safari/537.36";
+
+/// Tunables for the crawl: how many institutions/downloads run in flight at once, how
+/// long a single request is allowed to hang before it's abandoned, and what the shared
+/// HTTP client presents to the sites it talks to. A single stalled `.edu` host shouldn't
+/// be able to stall the whole run, and a few embed endpoints only respond to requests
+/// that look like a real browser.
+#[derive(Debug, Clone)]
+pub struct CrawlConfig {
+    pub concurrency: usize,
+    pub request_timeout: Duration,
+    pub user_agent: String,
+    pub extra_headers: HeaderMap,
+    pub cookies_file: Option<PathBuf>,
+}
+
+impl CrawlConfig {
+    /// Reads concurrency, timeout, user-agent, extra header, and cookie jar settings
+    /// from the environment, falling back to sane defaults when unset or unparsable.
+    ///
+    /// - `CRAWL_CONCURRENCY`: how many fetches/downloads run at once (default 8).
+    /// - `REQUEST_TIMEOUT_SECS`: per-request timeout in seconds (default 30).
+    /// - `CRAWL_USER_AGENT`: overrides the default desktop-Chrome user agent.
+    /// - `CRAWL_EXTRA_HEADERS`: `Name:Value` pairs separated by `;`, e.g.
+    ///   `X-Requested-With:XMLHttpRequest;Accept-Language:en-US`.
+    /// - `CRAWL_COOKIES_FILE`: path to a Netscape-format `cookies.txt` to send along.
+    pub fn from_env() -> Self {
+        let concurrency = std::env::var(CONCURRENCY_ENV)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_CONCURRENCY);
+        let request_timeout = std::env::var(REQUEST_TIMEOUT_ENV)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(DEFAULT_REQUEST_TIMEOUT_SECS));
+        let user_agent = std::env::var(USER_AGENT_ENV).unwrap_or_else(|_| DEFAULT_USER_AGENT.to_string());
+        let extra_headers = std::env::var(EXTRA_HEADERS_ENV)
+            .ok()
+            .map(|v| parse_extra_headers(&v))
+            .unwrap_or_default();
+        let cookies_file = std::env::var(COOKIES_FILE_ENV).ok().map(PathBuf::from);
+
+        CrawlConfig {
+            concurrency,
+            request_timeout,
+            user_agent,
+            extra_headers,
+            cookies_file,
+        }
+    }
+
+    /// Builds the shared client used by both the HTML crawl and the download paths, so
+    /// every request carries the same user-agent, extra headers, and cookie jar.
+    pub fn build_client(&self) -> Result<reqwest::Client, Box<dyn std::error::Error>> {
+        let mut builder = reqwest::Client::builder()
+            .timeout(self.request_timeout)
+            .user_agent(self.user_agent.clone())
+            .default_headers(self.extra_headers.clone());
+
+        if let Some(cookies_file) = &self.cookies_file {
+            builder = builder.cookie_provider(cookies::load_netscape_jar(cookies_file)?);
+        }
+
+        Ok(builder.build()?)
+    }
+
+    /// Builds a blocking client with the same user-agent, extra headers, and cookie jar as
+    /// [`build_client`](Self::build_client), for use by the (still-synchronous) `SiteHandler`
+    /// resolution paths that hit session-gated video config endpoints.
+    pub fn build_blocking_client(&self) -> Result<reqwest::blocking::Client, Box<dyn std::error::Error>> {
+        let mut builder = reqwest::blocking::Client::builder()
+            .timeout(self.request_timeout)
+            .user_agent(self.user_agent.clone())
+            .default_headers(self.extra_headers.clone());
+
+        if let Some(cookies_file) = &self.cookies_file {
+            builder = builder.cookie_provider(cookies::load_netscape_jar(cookies_file)?);
+        }
+
+        Ok(builder.build()?)
+    }
+}
+
+fn parse_extra_headers(raw: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+
+    for pair in raw.split(';') {
+        let pair = pair.trim();
+        if pair.is_empty() {
+            continue;
+        }
+        let Some((name, value)) = pair.split_once(':') else {
+            println!("Skipping malformed extra header (expected Name:Value): {}", pair);
+            continue;
+        };
+
+        match (HeaderName::try_from(name.trim()), HeaderValue::try_from(value.trim())) {
+            (Ok(name), Ok(value)) => {
+                headers.insert(name, value);
+            }
+            _ => println!("Skipping invalid extra header: {}", pair),
+        }
+    }
+
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_extra_headers_parses_multiple_semicolon_separated_pairs() {
+        let headers = parse_extra_headers("X-Requested-With:XMLHttpRequest;Accept-Language:en-US");
+
+        assert_eq!(headers.get("x-requested-with").unwrap(), "XMLHttpRequest");
+        assert_eq!(headers.get("accept-language").unwrap(), "en-US");
+        assert_eq!(headers.len(), 2);
+    }
+
+    #[test]
+    fn parse_extra_headers_trims_whitespace_around_names_and_values() {
+        let headers = parse_extra_headers(" X-Foo : bar ; X-Baz : qux ");
+
+        assert_eq!(headers.get("x-foo").unwrap(), "bar");
+        assert_eq!(headers.get("x-baz").unwrap(), "qux");
+    }
+
+    #[test]
+    fn parse_extra_headers_skips_malformed_and_empty_pairs() {
+        let headers = parse_extra_headers("no-colon-here;;X-Ok:yes");
+
+        assert_eq!(headers.len(), 1);
+        assert_eq!(headers.get("x-ok").unwrap(), "yes");
+    }
+
+    #[test]
+    fn parse_extra_headers_on_empty_input_is_empty() {
+        assert!(parse_extra_headers("").is_empty());
+    }
+}
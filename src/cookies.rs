@@ -0,0 +1,39 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use reqwest::cookie::Jar;
+use url::Url;
+
+/// Parses a Netscape-format `cookies.txt` file into a cookie jar reqwest can attach to
+/// its client, so session-gated video config endpoints can be reached.
+pub fn load_netscape_jar(cookies_file: &Path) -> Result<Arc<Jar>, Box<dyn Error>> {
+    let jar = Jar::default();
+
+    for line in fs::read_to_string(cookies_file)?.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        // `#HttpOnly_<domain>...` is a real data line: exporters (curl, browser extensions)
+        // prefix it that way to mark the cookie HttpOnly. Only a bare `#` is a comment.
+        let line = line.strip_prefix("#HttpOnly_").unwrap_or(line);
+        if line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split('\t').collect();
+        let [domain, _include_subdomains, _path, _secure, _expires, name, value] = fields[..] else {
+            println!("Skipping malformed cookies.txt line: {}", line);
+            continue;
+        };
+
+        let host = domain.trim_start_matches('.');
+        let cookie_url = Url::parse(&format!("https://{}", host))?;
+        jar.add_cookie_str(&format!("{}={}", name, value), &cookie_url);
+    }
+
+    Ok(Arc::new(jar))
+}
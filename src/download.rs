@@ -0,0 +1,69 @@
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use futures_util::StreamExt;
+use indicatif::{ProgressBar, ProgressStyle};
+use reqwest::header::RANGE;
+use reqwest::StatusCode;
+use tokio::fs::{self, OpenOptions};
+use tokio::io::AsyncWriteExt;
+
+/// Streams `video_url` to `output_path`, showing a progress bar and resuming a previous
+/// partial download if one is found.
+///
+/// The body is written to a `<output_path>.part` file as it streams in and only renamed
+/// to the final name once the download completes successfully, so an interrupted run
+/// leaves a resumable `.part` file rather than a truncated final file.
+pub async fn download_video(client: &reqwest::Client, video_url: &str, output_path: &Path) -> Result<(), Box<dyn Error>> {
+    let part_path = part_path_for(output_path);
+    let existing_size = fs::metadata(&part_path).await.map(|m| m.len()).unwrap_or(0);
+
+    let mut request = client.get(video_url);
+    if existing_size > 0 {
+        request = request.header(RANGE, format!("bytes={}-", existing_size));
+    }
+    let response = request.send().await?;
+
+    let resuming = existing_size > 0 && response.status() == StatusCode::PARTIAL_CONTENT;
+    let total_size = existing_size.saturating_mul(resuming as u64) + response.content_length().unwrap_or(0);
+
+    let progress_bar = ProgressBar::new(total_size);
+    progress_bar.set_style(
+        ProgressStyle::with_template("{msg} [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})")?
+            .progress_chars("=> "),
+    );
+    progress_bar.set_message(
+        output_path
+            .file_name()
+            .and_then(|f| f.to_str())
+            .unwrap_or("video")
+            .to_string(),
+    );
+    if resuming {
+        progress_bar.inc(existing_size);
+    }
+
+    let mut file = if resuming {
+        OpenOptions::new().append(true).open(&part_path).await?
+    } else {
+        fs::File::create(&part_path).await?
+    };
+
+    let mut body = response.bytes_stream();
+    while let Some(chunk) = body.next().await {
+        let chunk = chunk?;
+        file.write_all(&chunk).await?;
+        progress_bar.inc(chunk.len() as u64);
+    }
+    progress_bar.finish_and_clear();
+
+    fs::rename(&part_path, output_path).await?;
+    println!("Downloaded video from {} to {}", video_url, output_path.display());
+    Ok(())
+}
+
+fn part_path_for(output_path: &Path) -> PathBuf {
+    let mut part_path = output_path.as_os_str().to_os_string();
+    part_path.push(".part");
+    PathBuf::from(part_path)
+}
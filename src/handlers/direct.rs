@@ -0,0 +1,39 @@
+use std::error::Error;
+use std::path::Path;
+use url::Url;
+
+use super::{extract_src, MediaSource, SiteHandler};
+
+/// Resolves plain `<video src="...">` elements, where the src already points at a playable
+/// file (mp4, webm, m3u8, ...) and needs no further lookup.
+pub struct DirectVideoHandler;
+
+impl SiteHandler for DirectVideoHandler {
+    fn name(&self) -> &'static str {
+        "direct"
+    }
+
+    fn matches(&self, element_html: &str) -> bool {
+        extract_src(element_html, "video").is_some()
+    }
+
+    fn resolve(
+        &self,
+        element_html: &str,
+        page_url: &Url,
+        _client: &reqwest::blocking::Client,
+    ) -> Result<Vec<MediaSource>, Box<dyn Error>> {
+        let src = extract_src(element_html, "video").ok_or("No video src to resolve")?;
+        let video_url = page_url.join(&src)?;
+        let filename = Path::new(src.as_str())
+            .file_name()
+            .and_then(|f| f.to_str())
+            .ok_or("Could not derive a filename from the video src")?
+            .to_string();
+
+        Ok(vec![MediaSource {
+            url: video_url.to_string(),
+            filename,
+        }])
+    }
+}
@@ -0,0 +1,47 @@
+use std::error::Error;
+use std::path::Path;
+use regex::Regex;
+use url::Url;
+
+use super::{MediaSource, SiteHandler};
+
+/// Resolves generic JWPlayer embeds, where the page carries an inline `<script>` calling
+/// `jwplayer(...).setup({ file: "..." })` rather than a `<video>`/`<iframe>` src.
+pub struct JwPlayerHandler;
+
+fn file_regex() -> Regex {
+    Regex::new(r#"jwplayer\([^)]*\)\.setup\(\s*\{[^}]*?file\s*:\s*["']([^"']+)["']"#).unwrap()
+}
+
+impl SiteHandler for JwPlayerHandler {
+    fn name(&self) -> &'static str {
+        "jwplayer"
+    }
+
+    fn matches(&self, element_html: &str) -> bool {
+        element_html.contains("jwplayer(") && file_regex().is_match(element_html)
+    }
+
+    fn resolve(
+        &self,
+        element_html: &str,
+        page_url: &Url,
+        _client: &reqwest::blocking::Client,
+    ) -> Result<Vec<MediaSource>, Box<dyn Error>> {
+        let captures = file_regex()
+            .captures(element_html)
+            .ok_or("No jwplayer setup({ file: ... }) found in script")?;
+        let file_src = &captures[1];
+        let video_url = page_url.join(file_src)?;
+        let filename = Path::new(file_src)
+            .file_name()
+            .and_then(|f| f.to_str())
+            .unwrap_or("jwplayer_video.mp4")
+            .to_string();
+
+        Ok(vec![MediaSource {
+            url: video_url.to_string(),
+            filename,
+        }])
+    }
+}
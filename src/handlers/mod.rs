@@ -0,0 +1,71 @@
+mod direct;
+mod jwplayer;
+mod vimeo;
+mod youtube;
+
+use std::error::Error;
+use scraper::{Html, Selector};
+use url::Url;
+
+/// A media file discovered on an institution's page, ready to be handed to the downloader.
+#[derive(Debug, Clone)]
+pub struct MediaSource {
+    pub url: String,
+    pub filename: String,
+}
+
+/// A single video hosting/embed pattern the crawler knows how to resolve.
+///
+/// Implementations are handed the outer HTML of one extracted `video`/`iframe`/`script`
+/// element and the page it came from. `page_url` must be used (via `page_url.join`) to
+/// resolve whatever `src`/`file` the element carries, since CMS-driven sites commonly
+/// serve relative paths (`/media/foo.mp4`) or protocol-relative URLs (`//host/foo.mp4`).
+pub trait SiteHandler {
+    /// Short, stable identifier for this provider (e.g. `"vimeo"`), recorded in the
+    /// media manifest so discovered sources can be traced back to how they were found.
+    fn name(&self) -> &'static str;
+
+    /// Returns true if this handler knows how to resolve the given embed element.
+    fn matches(&self, element_html: &str) -> bool;
+
+    /// Resolves the embed element into concrete, downloadable media sources. `client` is
+    /// the crawl's shared (configured user-agent/headers/cookie jar) blocking client, so
+    /// lookups against session-gated video config endpoints go out the same way the page
+    /// fetch did, rather than as an anonymous request.
+    fn resolve(
+        &self,
+        element_html: &str,
+        page_url: &Url,
+        client: &reqwest::blocking::Client,
+    ) -> Result<Vec<MediaSource>, Box<dyn Error>>;
+}
+
+/// Returns the registered handlers in the order they should be tried.
+///
+/// New site support should be added here rather than back in the crawl loop. Order
+/// matters: more specific handlers (named providers) come before the generic fallbacks.
+pub fn registry() -> Vec<Box<dyn SiteHandler>> {
+    vec![
+        Box::new(vimeo::VimeoHandler),
+        Box::new(youtube::YoutubeHandler),
+        Box::new(direct::DirectVideoHandler),
+        Box::new(jwplayer::JwPlayerHandler),
+    ]
+}
+
+/// Finds the first registered handler that recognizes `element_html`, if any.
+pub fn find_handler<'a>(handlers: &'a [Box<dyn SiteHandler>], element_html: &str) -> Option<&'a dyn SiteHandler> {
+    handlers.iter().find(|h| h.matches(element_html)).map(|h| h.as_ref())
+}
+
+/// Pulls the `src` attribute off the first `tag` element in `element_html`, if present.
+pub(crate) fn extract_src(element_html: &str, tag: &str) -> Option<String> {
+    let fragment = Html::parse_fragment(element_html);
+    let selector = Selector::parse(tag).ok()?;
+    fragment
+        .select(&selector)
+        .next()?
+        .value()
+        .attr("src")
+        .map(|s| s.to_string())
+}
@@ -0,0 +1,54 @@
+use std::error::Error;
+use serde_json::Value;
+use url::Url;
+
+use super::{extract_src, MediaSource, SiteHandler};
+
+/// Resolves `player.vimeo.com` iframe embeds via Vimeo's public `/config` JSON endpoint.
+pub struct VimeoHandler;
+
+impl SiteHandler for VimeoHandler {
+    fn name(&self) -> &'static str {
+        "vimeo"
+    }
+
+    fn matches(&self, element_html: &str) -> bool {
+        extract_src(element_html, "iframe").is_some_and(|src| src.contains("vimeo.com"))
+    }
+
+    fn resolve(
+        &self,
+        element_html: &str,
+        page_url: &Url,
+        client: &reqwest::blocking::Client,
+    ) -> Result<Vec<MediaSource>, Box<dyn Error>> {
+        let src = extract_src(element_html, "iframe").ok_or("No iframe src to resolve")?;
+        let vimeo_url = page_url.join(&src)?;
+        let vimeo_id = vimeo_url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .ok_or("Vimeo URL has no path segments")?
+            .to_string();
+        let vimeo_api_url = format!("https://player.vimeo.com/video/{}/config", vimeo_id);
+
+        let response = client.get(&vimeo_api_url).send()?;
+        let vimeo_data: Value = response.json()?;
+        // `progressive` is an array of quality variants, not a single file; pick the
+        // highest-resolution one.
+        let progressive = vimeo_data["request"]["files"]["progressive"]
+            .as_array()
+            .ok_or("Vimeo config response had no progressive file array")?;
+        let best_variant = progressive
+            .iter()
+            .max_by_key(|variant| variant["width"].as_u64().unwrap_or(0) * variant["height"].as_u64().unwrap_or(0))
+            .ok_or("Vimeo config response had no progressive file variants")?;
+        let video_src = best_variant["url"]
+            .as_str()
+            .ok_or("Vimeo progressive variant had no url")?;
+
+        Ok(vec![MediaSource {
+            url: video_src.to_string(),
+            filename: format!("vimeo_{}.mp4", vimeo_id),
+        }])
+    }
+}
@@ -0,0 +1,72 @@
+use std::error::Error;
+use regex::Regex;
+use serde_json::Value;
+use url::Url;
+
+use super::{extract_src, MediaSource, SiteHandler};
+
+/// Resolves `youtube.com`/`youtu.be` embeds by scraping `ytInitialPlayerResponse` off the
+/// watch page. Only covers formats YouTube serves with a plain (unciphered) `url` field;
+/// signature-ciphered formats are left for a future handler.
+pub struct YoutubeHandler;
+
+impl SiteHandler for YoutubeHandler {
+    fn name(&self) -> &'static str {
+        "youtube"
+    }
+
+    fn matches(&self, element_html: &str) -> bool {
+        extract_src(element_html, "iframe")
+            .is_some_and(|src| src.contains("youtube.com") || src.contains("youtu.be"))
+    }
+
+    fn resolve(
+        &self,
+        element_html: &str,
+        page_url: &Url,
+        client: &reqwest::blocking::Client,
+    ) -> Result<Vec<MediaSource>, Box<dyn Error>> {
+        let src = extract_src(element_html, "iframe").ok_or("No iframe src to resolve")?;
+        let embed_url = page_url.join(&src)?;
+        let video_id = extract_video_id(&embed_url).ok_or("Could not find a YouTube video id in the URL")?;
+        let watch_url = format!("https://www.youtube.com/watch?v={}", video_id);
+
+        let html = client.get(&watch_url).send()?.text()?;
+        let re = Regex::new(r"var ytInitialPlayerResponse\s*=\s*(\{.*?\});")?;
+        let captures = re
+            .captures(&html)
+            .ok_or("ytInitialPlayerResponse not found on watch page")?;
+        let player_response: Value = serde_json::from_str(&captures[1])?;
+
+        let formats = player_response["streamingData"]["formats"]
+            .as_array()
+            .ok_or("No streamingData.formats in player response")?;
+
+        let direct_url = formats
+            .iter()
+            .find_map(|f| f["url"].as_str())
+            .ok_or("No unciphered format available for this video")?;
+
+        Ok(vec![MediaSource {
+            url: direct_url.to_string(),
+            filename: format!("youtube_{}.mp4", video_id),
+        }])
+    }
+}
+
+fn extract_video_id(url: &Url) -> Option<String> {
+    if url.host_str() == Some("youtu.be") {
+        return url.path_segments()?.next().map(|s| s.to_string());
+    }
+    url.query_pairs()
+        .find(|(k, _)| k == "v")
+        .map(|(_, v)| v.to_string())
+        .or_else(|| {
+            let segments: Vec<&str> = url.path_segments()?.collect();
+            segments
+                .iter()
+                .position(|&s| s == "embed")
+                .and_then(|i| segments.get(i + 1))
+                .map(|s| s.to_string())
+        })
+}
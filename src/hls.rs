@@ -0,0 +1,218 @@
+use std::error::Error;
+use std::future::Future;
+use std::pin::Pin;
+
+use aes::Aes128;
+use cbc::cipher::{BlockModeDecrypt, KeyIvInit};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use url::Url;
+
+type Aes128CbcDec = cbc::Decryptor<Aes128>;
+
+/// The recursive future type returned by [`download_m3u8`] (master playlists resolve to a
+/// variant by calling themselves once more).
+type DownloadFuture<'a> = Pin<Box<dyn Future<Output = Result<(), Box<dyn Error>>> + Send + 'a>>;
+
+/// An AES-128 key/IV pair extracted from an `#EXT-X-KEY` tag. `iv` is `None` when the tag
+/// carried no `IV=` attribute, so the caller falls back to the media-sequence-derived IV
+/// instead of confusing an absent IV with a legitimate all-zero one.
+struct SegmentKey {
+    key: [u8; 16],
+    iv: Option<[u8; 16]>,
+}
+
+/// Downloads an HLS stream (master or media `.m3u8` playlist) and concatenates its
+/// segments into a single file at `output_path`.
+///
+/// Master playlists are resolved to their highest-bandwidth variant before the media
+/// playlist is fetched. `#EXT-X-KEY:METHOD=AES-128` segments are decrypted inline.
+pub fn download_m3u8<'a>(
+    client: &'a reqwest::Client,
+    playlist_url: &'a str,
+    output_path: &'a std::path::Path,
+) -> DownloadFuture<'a> {
+    Box::pin(async move {
+        let base_url = Url::parse(playlist_url)?;
+        let playlist_text = client.get(playlist_url).send().await?.text().await?;
+
+        if is_master_playlist(&playlist_text) {
+            let variant_url = highest_bandwidth_variant(&playlist_text, &base_url)
+                .ok_or("Master playlist had no variant stream URLs")?;
+            return download_m3u8(client, variant_url.as_str(), output_path).await;
+        }
+
+        let mut output_file = fs::File::create(output_path).await?;
+        let mut media_sequence: u128 = 0;
+        let mut active_key: Option<SegmentKey> = None;
+
+        for line in playlist_text.lines() {
+            let line = line.trim();
+
+            if let Some(attrs) = line.strip_prefix("#EXT-X-KEY:") {
+                active_key = parse_key_tag(client, attrs, &base_url).await?;
+                continue;
+            }
+
+            if let Some(value) = line.strip_prefix("#EXT-X-MEDIA-SEQUENCE:") {
+                media_sequence = value.trim().parse()?;
+                continue;
+            }
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let segment_url = base_url.join(line)?;
+            let segment_bytes = client.get(segment_url.as_str()).send().await?.bytes().await?.to_vec();
+
+            let decrypted = match &active_key {
+                Some(segment_key) => decrypt_segment(segment_bytes, segment_key, media_sequence)?,
+                None => segment_bytes,
+            };
+
+            output_file.write_all(&decrypted).await?;
+            media_sequence += 1;
+        }
+
+        println!("Downloaded HLS stream from {} to {}", playlist_url, output_path.display());
+        Ok(())
+    })
+}
+
+fn is_master_playlist(playlist_text: &str) -> bool {
+    playlist_text.contains("#EXT-X-STREAM-INF:")
+}
+
+/// Picks the variant URL following the `#EXT-X-STREAM-INF:` tag with the highest `BANDWIDTH`.
+fn highest_bandwidth_variant(playlist_text: &str, base_url: &Url) -> Option<Url> {
+    let mut best: Option<(u64, &str)> = None;
+    let mut lines = playlist_text.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        let Some(attrs) = line.trim().strip_prefix("#EXT-X-STREAM-INF:") else {
+            continue;
+        };
+        let Some(&variant_line) = lines.peek() else {
+            continue;
+        };
+        if variant_line.trim().is_empty() || variant_line.trim().starts_with('#') {
+            continue;
+        }
+
+        let bandwidth = attrs
+            .split(',')
+            .find_map(|attr| attr.strip_prefix("BANDWIDTH="))
+            .and_then(|b| b.trim().parse::<u64>().ok())
+            .unwrap_or(0);
+
+        if best.is_none_or(|(best_bandwidth, _)| bandwidth > best_bandwidth) {
+            best = Some((bandwidth, variant_line.trim()));
+        }
+    }
+
+    best.and_then(|(_, variant)| base_url.join(variant).ok())
+}
+
+/// Parses an `#EXT-X-KEY:` attribute list, returning `None` for `METHOD=NONE`.
+async fn parse_key_tag(
+    client: &reqwest::Client,
+    attrs: &str,
+    base_url: &Url,
+) -> Result<Option<SegmentKey>, Box<dyn Error>> {
+    let get_attr = |name: &str| {
+        attrs
+            .split(',')
+            .find_map(|attr| attr.strip_prefix(&format!("{}=", name)))
+            .map(|v| v.trim().trim_matches('"'))
+    };
+
+    match get_attr("METHOD") {
+        Some("AES-128") => (),
+        _ => return Ok(None),
+    }
+
+    let key_uri = get_attr("URI").ok_or("EXT-X-KEY is missing a URI")?;
+    let key_url = base_url.join(key_uri)?;
+    let key_bytes = client.get(key_url.as_str()).send().await?.bytes().await?;
+    let key: [u8; 16] = key_bytes
+        .as_ref()
+        .try_into()
+        .map_err(|_| "AES-128 key must be 16 bytes")?;
+
+    let iv = match get_attr("IV") {
+        Some(iv_hex) => Some(parse_iv_hex(iv_hex)?),
+        None => None,
+    };
+
+    Ok(Some(SegmentKey { key, iv }))
+}
+
+fn parse_iv_hex(iv_hex: &str) -> Result<[u8; 16], Box<dyn Error>> {
+    let hex_digits = iv_hex.trim_start_matches("0x").trim_start_matches("0X");
+    let bytes = hex::decode(hex_digits)?;
+    bytes.try_into().map_err(|_| "IV must decode to 16 bytes".into())
+}
+
+/// IV used when a key has no explicit `IV=` attribute: the segment's media sequence
+/// number as a big-endian 128-bit value.
+fn sequence_iv(media_sequence: u128) -> [u8; 16] {
+    media_sequence.to_be_bytes()
+}
+
+fn decrypt_segment(
+    mut segment_bytes: Vec<u8>,
+    segment_key: &SegmentKey,
+    media_sequence: u128,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let iv = segment_key.iv.unwrap_or_else(|| sequence_iv(media_sequence));
+
+    let decryptor = Aes128CbcDec::new(&segment_key.key.into(), &iv.into());
+    let plaintext_len = decryptor
+        .decrypt_padded::<cbc::cipher::block_padding::Pkcs7>(&mut segment_bytes)
+        .map_err(|e| format!("Failed to decrypt HLS segment: {}", e))?
+        .len();
+    segment_bytes.truncate(plaintext_len);
+
+    Ok(segment_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highest_bandwidth_variant_picks_the_highest_bandwidth_line() {
+        let playlist = "#EXTM3U\n\
+             #EXT-X-STREAM-INF:BANDWIDTH=800000\n\
+             low/index.m3u8\n\
+             #EXT-X-STREAM-INF:BANDWIDTH=2500000\n\
+             high/index.m3u8\n\
+             #EXT-X-STREAM-INF:BANDWIDTH=1200000\n\
+             mid/index.m3u8\n";
+        let base_url = Url::parse("https://example.edu/video/master.m3u8").unwrap();
+
+        let variant = highest_bandwidth_variant(playlist, &base_url).unwrap();
+
+        assert_eq!(variant.as_str(), "https://example.edu/video/high/index.m3u8");
+    }
+
+    #[test]
+    fn highest_bandwidth_variant_is_none_without_any_stream_inf() {
+        let playlist = "#EXTM3U\n#EXT-X-VERSION:3\n";
+        let base_url = Url::parse("https://example.edu/video/master.m3u8").unwrap();
+
+        assert!(highest_bandwidth_variant(playlist, &base_url).is_none());
+    }
+
+    #[test]
+    fn parse_iv_hex_accepts_an_0x_prefixed_16_byte_value() {
+        let iv = parse_iv_hex("0x000102030405060708090a0b0c0d0e0f").unwrap();
+        assert_eq!(iv, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+    }
+
+    #[test]
+    fn parse_iv_hex_rejects_a_short_value() {
+        assert!(parse_iv_hex("0x0102").is_err());
+    }
+}
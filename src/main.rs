@@ -1,31 +1,54 @@
+mod browser;
+mod config;
+mod cookies;
+mod download;
+mod handlers;
+mod hls;
+mod manifest;
+
 use csv::{ReaderBuilder, WriterBuilder};
 use std::error::Error;
 use url::{Url, ParseError};
 use std::str;
 use std::fs;
 use std::path::Path;
-use reqwest;
+use std::sync::Arc;
 use regex::Regex;
 use scraper::{Html, Selector};
-use std::io::Write;
-use serde_json::Value;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use config::CrawlConfig;
+use manifest::ManifestEntry;
 
-fn main() -> Result<(), Box<dyn Error>> {
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
     let input_file_name = "resource/hd2023.csv";
     let output_file_name = "resource/crawler.csv";
     let parent_dir = "output";
+    let manifest_path = Path::new("resource/media.csv");
+    let config = CrawlConfig::from_env();
 
     // Step 1: Create crawler.csv if it doesn't exist
     create_crawler_csv(input_file_name, output_file_name)?;
 
     // Step 2: Create output folders if they don't exist
-    create_output_folders(output_file_name, parent_dir)?;
-
-    // Step 3: Process videos in HTML files
-    process_videos_in_html(parent_dir)?;
-
-    // Step 4: Download videos from the source
-    download_videos(parent_dir)?;
+    create_output_folders(output_file_name, parent_dir, &config).await?;
+
+    // Step 3: Resolve videos found in the fetched HTML into the media manifest. Handler
+    // resolution still uses blocking reqwest internally, so this runs on a blocking
+    // worker thread rather than tying up an async one.
+    let parent_dir_owned = parent_dir.to_string();
+    let manifest_path_owned = manifest_path.to_path_buf();
+    let config_owned = config.clone();
+    tokio::task::spawn_blocking(move || {
+        build_manifest(&parent_dir_owned, &manifest_path_owned, &config_owned).map_err(|e| e.to_string())
+    })
+    .await?
+    .map_err(|e| -> Box<dyn Error> { e.into() })?;
+
+    // Step 4: Download the media recorded in the manifest
+    download_videos(parent_dir, &config, manifest_path).await?;
 
     Ok(())
 }
@@ -57,7 +80,7 @@ fn create_crawler_csv(input_file_name: &str, output_file_name: &str) -> Result<(
         .ok_or("INSTNM column not found")?;
 
     // Write the headers to the output file
-    wtr.write_record(&["WEBADDR", "INSTNM"])?;
+    wtr.write_record(["WEBADDR", "INSTNM"])?;
 
     // Iterate over the records and process the URLs
     for result in rdr.byte_records() {
@@ -69,7 +92,7 @@ fn create_crawler_csv(input_file_name: &str, output_file_name: &str) -> Result<(
                             Ok(full_url) => {
                                 if let Some(instnm) = record.get(instnm_index) {
                                     let instnm_str = str::from_utf8(instnm).unwrap_or("Invalid UTF-8");
-                                    wtr.write_record(&[full_url.as_str(), instnm_str])?;
+                                    wtr.write_record([full_url.as_str(), instnm_str])?;
                                 }
                             }
                             Err(e) => println!("Error processing URL: {}", e),
@@ -86,7 +109,9 @@ fn create_crawler_csv(input_file_name: &str, output_file_name: &str) -> Result<(
     Ok(())
 }
 
-fn create_output_folders(output_file_name: &str, parent_dir: &str) -> Result<(), Box<dyn Error>> {
+/// Fetches and saves each institution's homepage in parallel, bounded by
+/// `config.concurrency` so one dead `.edu` host can't stall the whole run.
+async fn create_output_folders(output_file_name: &str, parent_dir: &str, config: &CrawlConfig) -> Result<(), Box<dyn Error>> {
     // Create the parent output directory if it doesn't exist
     if !Path::new(parent_dir).exists() {
         fs::create_dir(parent_dir)?;
@@ -106,67 +131,173 @@ fn create_output_folders(output_file_name: &str, parent_dir: &str) -> Result<(),
     let instnm_index = headers.iter().position(|h| h == "INSTNM")
         .ok_or("INSTNM column not found")?;
 
-    // Iterate over the records and process the URLs
+    let client = Arc::new(config.build_client()?);
+    let semaphore = Arc::new(Semaphore::new(config.concurrency));
+    let mut tasks = JoinSet::new();
+
+    // Fan out one fetch task per institution and let the semaphore throttle how many
+    // run at once.
     for result in rdr.byte_records() {
-        match result {
-            Ok(record) => {
-                if let Some(raw_url) = record.get(webaddr_index) {
-                    if let Ok(url) = str::from_utf8(raw_url) {
-                        match ensure_https_scheme(url) {
-                            Ok(full_url) => {
-                                if let Some(instnm) = record.get(instnm_index) {
-                                    let instnm_str = str::from_utf8(instnm).unwrap_or("Invalid UTF-8");
-                                    let sanitized_instnm = sanitize_folder_name(instnm_str.trim());
-
-                                    // Create folder named after INSTNM inside the parent directory if it doesn't exist
-                                    let folder_name = format!("{}/{}", parent_dir, sanitized_instnm);
-                                    let html_output_path = format!("{}/index.html", folder_name);
-
-                                    // Skip fetching if the folder and HTML output already exist
-                                    if Path::new(&folder_name).exists() && Path::new(&html_output_path).exists() {
-                                        println!("Skipping {} as it already exists with index.html.", folder_name);
-                                        continue;
-                                    }
-
-                                    if !Path::new(&folder_name).exists() {
-                                        fs::create_dir(&folder_name)?;
-                                    }
-
-                                    if let Ok(html_content) = fetch_html(&full_url) {
-                                        fs::write(html_output_path, html_content)?;
-                                    } else {
-                                        println!("Skipping {} due to fetch error.", full_url);
-                                    }
-                                }
-                            }
-                            Err(e) => println!("Error processing URL: {}", e),
-                        }
-                    } else {
-                        println!("Error converting raw URL to UTF-8");
-                    }
-                }
+        let record = match result {
+            Ok(record) => record,
+            Err(e) => {
+                println!("Error reading record: {}", e);
+                continue;
             }
-            Err(e) => println!("Error reading record: {}", e),
-        }
+        };
+
+        let Some(raw_url) = record.get(webaddr_index) else { continue };
+        let Ok(url) = str::from_utf8(raw_url) else {
+            println!("Error converting raw URL to UTF-8");
+            continue;
+        };
+        let full_url = match ensure_https_scheme(url) {
+            Ok(full_url) => full_url,
+            Err(e) => {
+                println!("Error processing URL: {}", e);
+                continue;
+            }
+        };
+        let Some(instnm) = record.get(instnm_index) else { continue };
+        let instnm_str = str::from_utf8(instnm).unwrap_or("Invalid UTF-8").to_string();
+
+        let client = Arc::clone(&client);
+        let semaphore = Arc::clone(&semaphore);
+        let parent_dir = parent_dir.to_string();
+
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await.expect("semaphore closed");
+            fetch_and_save_institution(&client, &full_url, &instnm_str, &parent_dir).await;
+        });
+    }
+
+    while let Some(result) = tasks.join_next().await {
+        result?;
     }
 
     Ok(())
 }
 
-fn process_videos_in_html(parent_dir: &str) -> Result<(), Box<dyn Error>> {
-    // Iterate through each subdirectory in the parent directory
+async fn fetch_and_save_institution(client: &reqwest::Client, full_url: &Url, instnm_str: &str, parent_dir: &str) {
+    let sanitized_instnm = sanitize_folder_name(instnm_str.trim());
+
+    // Create folder named after INSTNM inside the parent directory if it doesn't exist
+    let folder_name = format!("{}/{}", parent_dir, sanitized_instnm);
+    let html_output_path = format!("{}/index.html", folder_name);
+    let page_url_path = format!("{}/page_url.txt", folder_name);
+
+    if !Path::new(&folder_name).exists() {
+        if let Err(e) = fs::create_dir(&folder_name) {
+            println!("Error creating folder {}: {}", folder_name, e);
+            return;
+        }
+    }
+    // Keep the source URL alongside index.html so later stages (the media manifest)
+    // can record provenance without re-reading crawler.csv.
+    if let Err(e) = fs::write(&page_url_path, full_url.as_str()) {
+        println!("Error writing {}: {}", page_url_path, e);
+    }
+
+    // Skip fetching if the HTML output already exists
+    if Path::new(&html_output_path).exists() {
+        println!("Skipping {} as it already exists with index.html.", folder_name);
+        return;
+    }
+
+    match fetch_rendered_or_plain_html(client, full_url).await {
+        Ok(html_content) => {
+            if let Err(e) = fs::write(html_output_path, html_content) {
+                println!("Error writing HTML for {}: {}", full_url, e);
+            }
+        }
+        Err(_) => println!("Skipping {} due to fetch error.", full_url),
+    }
+}
+
+/// Scans every fetched institution page for video embeds and resolves each one into a
+/// `ManifestEntry`, writing the full result set to `manifest_path`. This replaces the
+/// old per-element `video_N.html` files with a single structured dataset: provenance
+/// (institution, source page), what resolved it, and the media URL(s) it found.
+fn build_manifest(parent_dir: &str, manifest_path: &Path, config: &CrawlConfig) -> Result<(), Box<dyn Error>> {
+    let site_handlers = handlers::registry();
+    let client = config.build_blocking_client()?;
+    let mut entries = Vec::new();
+
     for entry in fs::read_dir(parent_dir)? {
         let entry = entry?;
         let subdir_path = entry.path();
-        if subdir_path.is_dir() {
-            let html_file_path = subdir_path.join("index.html");
-            if html_file_path.exists() {
-                let video_elements = extract_video_elements(&html_file_path)?;
-                save_video_elements(&video_elements, &subdir_path)?;
-            }
+        if !subdir_path.is_dir() {
+            continue;
+        }
+        let html_file_path = subdir_path.join("index.html");
+        if !html_file_path.exists() {
+            continue;
+        }
+
+        let instnm = subdir_path
+            .file_name()
+            .and_then(|f| f.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+        let page_url = fs::read_to_string(subdir_path.join("page_url.txt")).unwrap_or_else(|_| "unknown".to_string());
+        let page_url_for_resolve =
+            ensure_https_scheme(&page_url).unwrap_or_else(|_| Url::parse("about:blank").expect("static URL parses"));
+
+        for element_html in extract_video_elements(&html_file_path)? {
+            entries.extend(resolve_manifest_entries(
+                &site_handlers,
+                &client,
+                &instnm,
+                &page_url,
+                &page_url_for_resolve,
+                &element_html,
+            ));
         }
     }
-    Ok(())
+
+    manifest::write_all(manifest_path, &entries)
+}
+
+/// Resolves one extracted embed element into its manifest entries: one row per
+/// resolved media source, or a single unresolved/failed row if resolution found
+/// nothing or errored.
+fn resolve_manifest_entries(
+    site_handlers: &[Box<dyn handlers::SiteHandler>],
+    client: &reqwest::blocking::Client,
+    instnm: &str,
+    page_url: &str,
+    page_url_for_resolve: &Url,
+    element_html: &str,
+) -> Vec<ManifestEntry> {
+    let base = |provider: &str, status: &str| ManifestEntry {
+        instnm: instnm.to_string(),
+        page_url: page_url.to_string(),
+        provider: provider.to_string(),
+        media_url: String::new(),
+        format: "unknown".to_string(),
+        status: status.to_string(),
+        error: String::new(),
+    };
+
+    match handlers::find_handler(site_handlers, element_html) {
+        Some(handler) => match handler.resolve(element_html, page_url_for_resolve, client) {
+            Ok(media_sources) if !media_sources.is_empty() => media_sources
+                .into_iter()
+                .map(|media_source| ManifestEntry {
+                    media_url: media_source.url,
+                    format: manifest::detect_format(&media_source.filename).to_string(),
+                    status: "pending".to_string(),
+                    ..base(handler.name(), "pending")
+                })
+                .collect(),
+            Ok(_) => vec![base(handler.name(), "unresolved")],
+            Err(e) => vec![ManifestEntry {
+                error: e.to_string(),
+                ..base(handler.name(), "failed")
+            }],
+        },
+        None => vec![base("unknown", "unresolved")],
+    }
 }
 
 fn extract_video_elements(html_file_path: &Path) -> Result<Vec<String>, Box<dyn Error>> {
@@ -174,107 +305,103 @@ fn extract_video_elements(html_file_path: &Path) -> Result<Vec<String>, Box<dyn
     let html_content = fs::read_to_string(html_file_path)?;
     let document = Html::parse_document(&html_content);
 
-    // Find all video elements
-    let video_selector = Selector::parse("video, iframe").unwrap();
+    // Find all video elements, including inline scripts that may carry a JWPlayer setup.
+    // `script` matches every inline/external script on the page, so those are filtered down
+    // to ones that actually look like a JWPlayer embed before being treated as candidates -
+    // otherwise ordinary analytics/tracking scripts would flood the manifest as "unresolved".
+    let video_selector = Selector::parse("video, iframe, script").unwrap();
     let video_elements = document.select(&video_selector);
 
     // Extract video elements
     let mut video_elements_html = Vec::new();
     for video in video_elements {
-        video_elements_html.push(video.html());
+        let html = video.html();
+        if video.value().name() == "script" && !html.contains("jwplayer(") {
+            continue;
+        }
+        video_elements_html.push(html);
     }
 
     Ok(video_elements_html)
 }
 
-fn save_video_elements(video_elements: &[String], output_dir: &Path) -> Result<(), Box<dyn Error>> {
-    for (i, element) in video_elements.iter().enumerate() {
-        // Define the output file path
-        let output_file_path = output_dir.join(format!("video_{}.html", i + 1));
+/// Downloads every `pending` entry in the manifest in parallel, bounded by
+/// `config.concurrency`, then rewrites the manifest with each entry's final status.
+async fn download_videos(parent_dir: &str, config: &CrawlConfig, manifest_path: &Path) -> Result<(), Box<dyn Error>> {
+    let mut entries = manifest::read_all(manifest_path)?;
+    let client = Arc::new(config.build_client()?);
+    let semaphore = Arc::new(Semaphore::new(config.concurrency));
+    let mut tasks = JoinSet::new();
 
-        // Skip creation if the file already exists
-        if output_file_path.exists() {
-            println!("{} already exists. Skipping creation.", output_file_path.display());
+    for (index, entry) in entries.iter().enumerate() {
+        if entry.status != "pending" {
             continue;
         }
 
-        // Save the video element to a new HTML file
-        fs::write(output_file_path, element)?;
+        let client = Arc::clone(&client);
+        let semaphore = Arc::clone(&semaphore);
+        let parent_dir = parent_dir.to_string();
+        let entry = entry.clone();
+
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await.expect("semaphore closed");
+            let result = download_manifest_entry(&client, &entry, &parent_dir).await;
+            (index, result.map_err(|e| e.to_string()))
+        });
     }
-    Ok(())
+
+    while let Some(joined) = tasks.join_next().await {
+        let (index, result) = joined?;
+        match result {
+            Ok(()) => entries[index].status = "downloaded".to_string(),
+            Err(e) => {
+                entries[index].status = "failed".to_string();
+                entries[index].error = e;
+            }
+        }
+    }
+
+    manifest::write_all(manifest_path, &entries)
 }
 
-fn download_videos(parent_dir: &str) -> Result<(), Box<dyn Error>> {
-    // Iterate through each subdirectory in the parent directory
-    for entry in fs::read_dir(parent_dir)? {
-        let entry = entry?;
-        let subdir_path = entry.path();
-        if subdir_path.is_dir() {
-            for video_file in fs::read_dir(&subdir_path)? {
-                let video_file = video_file?;
-                let video_file_path = video_file.path();
-                if video_file_path.is_file() && video_file_path.file_name().unwrap().to_str().unwrap().starts_with("video_") {
-                    let video_html = fs::read_to_string(&video_file_path)?;
-                    let document = Html::parse_document(&video_html);
-                    let video_selector = Selector::parse("video").unwrap();
-                    let iframe_selector = Selector::parse("iframe").unwrap();
-
-                    if let Some(video_element) = document.select(&video_selector).next() {
-                        if let Some(src) = video_element.value().attr("src") {
-                            let video_url = ensure_https_scheme(src)?;
-                            let video_filename = Path::new(src).file_name().unwrap().to_str().unwrap();
-                            let video_output_path = subdir_path.join(video_filename);
-
-                            // Skip downloading if the video file already exists
-                            if video_output_path.exists() {
-                                println!("{} already exists. Skipping download.", video_output_path.display());
-                                continue;
-                            }
+async fn download_manifest_entry(client: &reqwest::Client, entry: &ManifestEntry, parent_dir: &str) -> Result<(), Box<dyn Error>> {
+    let subdir_path = Path::new(parent_dir).join(&entry.instnm);
+    let output_path = subdir_path.join(manifest_output_filename(entry));
 
-                            download_video(video_url.as_str(), &video_output_path)?;
-                        }
-                    } else if let Some(iframe_element) = document.select(&iframe_selector).next() {
-                        if let Some(src) = iframe_element.value().attr("src") {
-                            if src.contains("vimeo.com") {
-                                let vimeo_url = ensure_https_scheme(src)?;
-                                let vimeo_id = vimeo_url.path_segments().unwrap().last().unwrap();
-                                let vimeo_api_url = format!("https://player.vimeo.com/video/{}/config", vimeo_id);
-
-                                let response = reqwest::blocking::get(&vimeo_api_url)?;
-                                let vimeo_data: Value = response.json()?;
-                                let video_src = vimeo_data["request"]["files"]["progressive"]["url"].as_str().unwrap();
-                                let video_filename = format!("vimeo_{}.mp4", vimeo_id);
-                                let video_output_path = subdir_path.join(video_filename);
-
-                                // Skip downloading if the video file already exists
-                                if video_output_path.exists() {
-                                    println!("{} already exists. Skipping download.", video_output_path.display());
-                                    continue;
-                                }
+    // Skip downloading if the video file already exists
+    if output_path.exists() {
+        println!("{} already exists. Skipping download.", output_path.display());
+        return Ok(());
+    }
 
-                                download_video(video_src, &video_output_path)?;
-                            } else {
-                                println!("Video source URL for further examination: {}", src);
-                            }
-                        }
-                    }
-                }
-            }
-        }
+    if entry.format == "hls" {
+        hls::download_m3u8(client, &entry.media_url, &output_path).await
+    } else if entry.format == "dash" {
+        Err("DASH (.mpd) manifests are not downloadable yet".into())
+    } else {
+        download::download_video(client, &entry.media_url, &output_path).await
     }
-    Ok(())
 }
 
-fn download_video(video_url: &str, output_path: &Path) -> Result<(), Box<dyn Error>> {
-    let response = reqwest::blocking::get(video_url)?;
-    let mut file = fs::File::create(output_path)?;
-    let mut content = response.bytes()?;
-    file.write_all(&mut content)?;
-    println!("Downloaded video from {} to {}", video_url, output_path.display());
-    Ok(())
+/// Derives an output filename from a manifest entry's media URL, falling back to a
+/// provider-based name when the URL has no usable path segment.
+fn manifest_output_filename(entry: &ManifestEntry) -> String {
+    let basename = Url::parse(&entry.media_url)
+        .ok()
+        .and_then(|u| u.path_segments().and_then(|mut s| s.next_back()).map(|s| s.to_string()))
+        .filter(|s| !s.is_empty());
+
+    // HLS playlists are downloaded as the concatenation of their segments, so the
+    // output file is a transport stream, not a copy of the playlist itself.
+    match (entry.format.as_str(), basename) {
+        ("hls", Some(name)) => format!("{}.ts", name.trim_end_matches(".m3u8")),
+        ("hls", None) => format!("{}.ts", entry.provider),
+        (_, Some(name)) => name,
+        (_, None) => format!("{}_video", entry.provider),
+    }
 }
 
-fn ensure_https_scheme(url: &str) -> Result<Url, ParseError> {
+pub(crate) fn ensure_https_scheme(url: &str) -> Result<Url, ParseError> {
     let parsed_url = Url::parse(url);
     match parsed_url {
         Ok(url) => Ok(url),
@@ -285,14 +412,27 @@ fn ensure_https_scheme(url: &str) -> Result<Url, ParseError> {
     }
 }
 
-fn fetch_html(url: &Url) -> Result<String, reqwest::Error> {
-    let response = reqwest::blocking::get(url.as_str())?;
-    let html = response.text()?;
+async fn fetch_html(client: &reqwest::Client, url: &Url) -> Result<String, reqwest::Error> {
+    let response = client.get(url.as_str()).send().await?;
+    let html = response.text().await?;
     Ok(html)
 }
 
+/// Fetches `url` via a configured WebDriver session when available, so JS-injected
+/// players are present in the returned HTML; otherwise falls back to a plain fetch.
+async fn fetch_rendered_or_plain_html(client: &reqwest::Client, url: &Url) -> Result<String, Box<dyn Error>> {
+    // `Box<dyn Error>` from `fetch_rendered_html` isn't `Send`, so it can't be held across
+    // the `fetch_html` await below inside a `JoinSet`-spawned future; convert it to a
+    // `String` first and let `?` turn it back into a `Box<dyn Error>` on return.
+    let rendered = browser::fetch_rendered_html(url).await.map_err(|e| e.to_string())?;
+    match rendered {
+        Some(html) => Ok(html),
+        None => Ok(fetch_html(client, url).await?),
+    }
+}
+
 fn sanitize_folder_name(name: &str) -> String {
     let re = Regex::new(r"[^\w\s-]").unwrap();
     let sanitized_name = re.replace_all(name, "").to_string();
     sanitized_name.replace(" ", "_")
-}
\ No newline at end of file
+}
@@ -0,0 +1,134 @@
+use std::error::Error;
+use std::path::Path;
+
+use csv::{ReaderBuilder, WriterBuilder};
+
+const HEADERS: [&str; 7] = ["INSTNM", "PAGE_URL", "PROVIDER", "MEDIA_URL", "FORMAT", "STATUS", "ERROR"];
+
+/// One discovered (and, later, downloaded-or-not) piece of media, the unit of record in
+/// `media.csv`. Replaces the old per-element `video_N.html` files: this is the single
+/// structured dataset both the resolution stage writes and the download stage reads.
+#[derive(Debug, Clone)]
+pub struct ManifestEntry {
+    /// The sanitized institution folder name under `output/`.
+    pub instnm: String,
+    pub page_url: String,
+    pub provider: String,
+    pub media_url: String,
+    /// One of `mp4`, `hls`, `dash`, `unknown`.
+    pub format: String,
+    /// One of `pending`, `downloaded`, `failed`, `unresolved`.
+    pub status: String,
+    pub error: String,
+}
+
+/// Guesses a media format from a resolved source's filename extension.
+pub fn detect_format(filename: &str) -> &'static str {
+    match filename.rsplit('.').next().unwrap_or("").to_lowercase().as_str() {
+        "mp4" | "m4v" | "mov" | "webm" => "mp4",
+        "m3u8" => "hls",
+        "mpd" => "dash",
+        _ => "unknown",
+    }
+}
+
+/// Overwrites `manifest_path` with `entries`, the full result set for this run.
+pub fn write_all(manifest_path: &Path, entries: &[ManifestEntry]) -> Result<(), Box<dyn Error>> {
+    let mut wtr = WriterBuilder::new().has_headers(true).from_path(manifest_path)?;
+    wtr.write_record(HEADERS)?;
+    for entry in entries {
+        wtr.write_record([
+            &entry.instnm,
+            &entry.page_url,
+            &entry.provider,
+            &entry.media_url,
+            &entry.format,
+            &entry.status,
+            &entry.error,
+        ])?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Reads back the entries written by `write_all`.
+pub fn read_all(manifest_path: &Path) -> Result<Vec<ManifestEntry>, Box<dyn Error>> {
+    let mut rdr = ReaderBuilder::new().has_headers(true).from_path(manifest_path)?;
+    let mut entries = Vec::new();
+
+    for result in rdr.records() {
+        let record = result?;
+        entries.push(ManifestEntry {
+            instnm: record.get(0).unwrap_or_default().to_string(),
+            page_url: record.get(1).unwrap_or_default().to_string(),
+            provider: record.get(2).unwrap_or_default().to_string(),
+            media_url: record.get(3).unwrap_or_default().to_string(),
+            format: record.get(4).unwrap_or_default().to_string(),
+            status: record.get(5).unwrap_or_default().to_string(),
+            error: record.get(6).unwrap_or_default().to_string(),
+        });
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn sample_entry() -> ManifestEntry {
+        ManifestEntry {
+            instnm: "Example University".to_string(),
+            page_url: "https://example.edu/video".to_string(),
+            provider: "vimeo".to_string(),
+            media_url: "https://vimeo-cdn.example/123.mp4".to_string(),
+            format: "mp4".to_string(),
+            status: "pending".to_string(),
+            error: String::new(),
+        }
+    }
+
+    #[test]
+    fn write_all_then_read_all_round_trips_entries() {
+        let manifest_path = std::env::temp_dir().join("media_manifest_round_trip_test.csv");
+        let entries = vec![sample_entry()];
+
+        write_all(&manifest_path, &entries).unwrap();
+        let read_back = read_all(&manifest_path).unwrap();
+
+        fs::remove_file(&manifest_path).ok();
+
+        assert_eq!(read_back.len(), 1);
+        assert_eq!(read_back[0].instnm, entries[0].instnm);
+        assert_eq!(read_back[0].page_url, entries[0].page_url);
+        assert_eq!(read_back[0].provider, entries[0].provider);
+        assert_eq!(read_back[0].media_url, entries[0].media_url);
+        assert_eq!(read_back[0].format, entries[0].format);
+        assert_eq!(read_back[0].status, entries[0].status);
+        assert_eq!(read_back[0].error, entries[0].error);
+    }
+
+    #[test]
+    fn write_all_overwrites_rather_than_appends() {
+        let manifest_path = std::env::temp_dir().join("media_manifest_overwrite_test.csv");
+
+        write_all(&manifest_path, &[sample_entry(), sample_entry()]).unwrap();
+        write_all(&manifest_path, &[sample_entry()]).unwrap();
+        let read_back = read_all(&manifest_path).unwrap();
+
+        fs::remove_file(&manifest_path).ok();
+
+        assert_eq!(read_back.len(), 1);
+    }
+
+    #[test]
+    fn detect_format_covers_known_and_unknown_extensions() {
+        assert_eq!(detect_format("video.mp4"), "mp4");
+        assert_eq!(detect_format("video.M4V"), "mp4");
+        assert_eq!(detect_format("playlist.m3u8"), "hls");
+        assert_eq!(detect_format("manifest.mpd"), "dash");
+        assert_eq!(detect_format("video.unknownext"), "unknown");
+        assert_eq!(detect_format("no_extension"), "unknown");
+    }
+}